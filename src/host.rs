@@ -1,7 +1,19 @@
-use serde::Serialize;
+use serde::{de::DeserializeOwned, Serialize};
+use std::io::{BufRead, IsTerminal, Read};
+use std::iter::FusedIterator;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use tokio::io::{self, stdin, stdout, AsyncReadExt, AsyncWriteExt};
 use tokio::select;
 
+/// The maximum size in bytes of a single message sent from the host to the browser (1 MB).
+/// <https://developer.mozilla.org/en-US/docs/Mozilla/Add-ons/WebExtensions/Native_messaging#App_side>
+pub const MAX_TO_BROWSER: usize = 1024 * 1024;
+
+/// The maximum size in bytes of a single message sent from the browser to the host (4 GB),
+/// which is also the largest length the 4-byte prefix can express.
+pub const MAX_FROM_BROWSER: usize = u32::MAX as usize;
+
 /// Encodes a message according to the native messaging protocol.
 /// <https://developer.mozilla.org/en-US/docs/Mozilla/Add-ons/WebExtensions/Native_messaging#App_side>
 ///
@@ -39,6 +51,48 @@ where
     Ok(encoded_message)
 }
 
+/// Encodes a message according to the native messaging protocol directly into a writer,
+/// without allocating an intermediate frame buffer.
+///
+/// Returns the total number of bytes written, including the 4-byte length prefix.
+///
+/// # Examples
+///
+/// ```
+/// use native_messaging::host::{encode_message, encode_message_into};
+/// use serde_json::json;
+///
+/// let mut buffer = Vec::new();
+/// let written = encode_message_into(&json!({ "key": "value" }), &mut buffer).unwrap();
+/// assert_eq!(written as usize, buffer.len());
+/// assert_eq!(buffer, encode_message(&json!({ "key": "value" })).unwrap());
+/// ```
+///
+/// # Errors
+/// Returns an `io::Error` of kind `InvalidData` if serialization fails or the message is too
+/// long for the length prefix, or any error from writing.
+pub fn encode_message_into<T, W>(message_content: &T, writer: &mut W) -> io::Result<u32>
+where
+    T: Serialize,
+    W: std::io::Write,
+{
+    let encoded_content = serde_json::to_vec(message_content)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let content_length = u32::try_from(encoded_content.len())
+        .ok()
+        .filter(|length| *length <= u32::MAX - 4)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "message is too long for the length prefix",
+            )
+        })?;
+    writer.write_all(&content_length.to_ne_bytes())?;
+    writer.write_all(&encoded_content)?;
+
+    Ok(content_length + 4)
+}
+
 /// Asynchronously reads a message from stdin according to the native messaging protocol.
 ///
 /// Each message is prefixed with a 4-byte length in native byte order,
@@ -62,18 +116,237 @@ where
 /// # Errors
 /// Returns an `io::Error` if reading from stdin fails.
 pub async fn get_message() -> io::Result<String> {
+    let content_bytes = get_message_bytes().await?;
+    let message = String::from_utf8(content_bytes)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    Ok(message)
+}
+
+async fn get_message_bytes() -> io::Result<Vec<u8>> {
     let mut stdin = stdin();
     let mut length_bytes = [0u8; 4];
     stdin.read_exact(&mut length_bytes).await?;
     let message_length = u32::from_ne_bytes(length_bytes) as usize;
     let mut content_bytes = vec![0u8; message_length];
     stdin.read_exact(&mut content_bytes).await?;
+
+    Ok(content_bytes)
+}
+
+/// Reads only the 4-byte length prefix of the next message from a synchronous reader.
+///
+/// This is the first half of a two-phase read: inspect the declared length against your own
+/// policy, then call `read_message_body` to consume the payload. Oversized messages can be
+/// rejected before any buffer for the body is allocated.
+///
+/// # Examples
+///
+/// ```
+/// use native_messaging::host::{encode_message, read_message_header};
+/// use serde_json::json;
+/// use std::io::Cursor;
+///
+/// let frame = encode_message(&json!({ "key": "value" })).unwrap();
+/// let length = read_message_header(&mut Cursor::new(frame)).unwrap();
+/// assert_eq!(length as usize, r#"{"key":"value"}"#.len());
+/// ```
+///
+/// # Errors
+/// Returns an `io::Error` if fewer than 4 bytes can be read.
+pub fn read_message_header<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut length_bytes = [0u8; 4];
+    reader.read_exact(&mut length_bytes)?;
+
+    Ok(u32::from_ne_bytes(length_bytes))
+}
+
+/// Reads the body of a message whose length was obtained from `read_message_header`.
+///
+/// # Examples
+///
+/// ```
+/// use native_messaging::host::{encode_message, read_message_body, read_message_header};
+/// use serde_json::json;
+/// use std::io::Cursor;
+///
+/// let mut reader = Cursor::new(encode_message(&json!({ "key": "value" })).unwrap());
+/// let length = read_message_header(&mut reader).unwrap();
+/// let message = read_message_body(&mut reader, length, 1024).unwrap();
+/// assert_eq!(message, r#"{"key":"value"}"#);
+/// ```
+///
+/// # Errors
+/// Returns an `io::Error` of kind `InvalidData` if `length` exceeds `max_size` or the body
+/// is not valid UTF-8, or any error from reading the body.
+pub fn read_message_body<R: Read>(
+    reader: &mut R,
+    length: u32,
+    max_size: usize,
+) -> io::Result<String> {
+    let message_length = check_message_length(length, max_size)?;
+    let mut content_bytes = vec![0u8; message_length];
+    reader.read_exact(&mut content_bytes)?;
     let message = String::from_utf8(content_bytes)
         .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
 
     Ok(message)
 }
 
+/// Decodes the next message from a synchronous reader straight into a `serde_json::Value`.
+///
+/// Unlike calling `read_message_body` followed by `serde_json::from_str`, the JSON is parsed
+/// directly from the reader without an intermediate `String`.
+///
+/// # Examples
+///
+/// ```
+/// use native_messaging::host::{decode_message_into_value, encode_message};
+/// use serde_json::json;
+/// use std::io::Cursor;
+///
+/// let mut reader = Cursor::new(encode_message(&json!({ "key": "value" })).unwrap());
+/// let value = decode_message_into_value(&mut reader, 1024).unwrap();
+/// assert_eq!(value, json!({ "key": "value" }));
+/// ```
+///
+/// # Errors
+/// Returns an `io::Error` of kind `InvalidData` if the declared length exceeds `max_size` or
+/// the body is not valid JSON, or any error from reading the message.
+pub fn decode_message_into_value<R: Read>(
+    reader: &mut R,
+    max_size: usize,
+) -> io::Result<serde_json::Value> {
+    let length = read_message_header(reader)?;
+    let message_length = check_message_length(length, max_size)?;
+    let value = serde_json::from_reader(reader.take(message_length as u64))?;
+
+    Ok(value)
+}
+
+fn check_message_length(length: u32, max_size: usize) -> io::Result<usize> {
+    let message_length = length as usize;
+    if message_length > max_size {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "message length {} exceeds the maximum of {} bytes",
+                message_length, max_size
+            ),
+        ));
+    }
+
+    Ok(message_length)
+}
+
+/// An iterator over messages read from a synchronous reader according to the native
+/// messaging protocol.
+///
+/// Each item is the UTF-8 JSON content of one message. Iteration ends when the reader reaches
+/// end-of-file between messages, so recorded sessions can be replayed with a `for` loop.
+/// After an error the stream position is no longer at a frame boundary, so the iterator is
+/// fused and returns `None` from then on.
+///
+/// # Examples
+///
+/// ```
+/// use native_messaging::host::{encode_message, FramedReader, MAX_FROM_BROWSER};
+/// use serde_json::json;
+///
+/// let mut recording = encode_message(&json!({ "n": 1 })).unwrap();
+/// recording.extend(encode_message(&json!({ "n": 2 })).unwrap());
+///
+/// let messages: Vec<String> = FramedReader::new(recording.as_slice(), MAX_FROM_BROWSER)
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+/// assert_eq!(messages, vec![r#"{"n":1}"#, r#"{"n":2}"#]);
+/// ```
+pub struct FramedReader<R: Read> {
+    reader: R,
+    max_size: usize,
+    done: bool,
+}
+
+impl<R: Read> FramedReader<R> {
+    /// Creates an iterator over the messages in `reader`, rejecting messages longer than
+    /// `max_size` bytes.
+    pub fn new(reader: R, max_size: usize) -> Self {
+        FramedReader {
+            reader,
+            max_size,
+            done: false,
+        }
+    }
+
+    fn read_next(&mut self) -> Option<io::Result<String>> {
+        let mut length_bytes = [0u8; 4];
+        let mut filled = 0;
+        while filled < length_bytes.len() {
+            match self.reader.read(&mut length_bytes[filled..]) {
+                Ok(0) if filled == 0 => return None,
+                Ok(0) => return Some(Err(io::ErrorKind::UnexpectedEof.into())),
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        let length = u32::from_ne_bytes(length_bytes);
+
+        Some(read_message_body(&mut self.reader, length, self.max_size))
+    }
+}
+
+impl<R: Read> Iterator for FramedReader<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let item = self.read_next();
+        if !matches!(item, Some(Ok(_))) {
+            self.done = true;
+        }
+        item
+    }
+}
+
+impl<R: Read> FusedIterator for FramedReader<R> {}
+
+/// Writes messages to a synchronous writer according to the native messaging protocol,
+/// flushing after each one.
+///
+/// # Examples
+///
+/// ```
+/// use native_messaging::host::{encode_message, FramedWriter};
+/// use serde_json::json;
+///
+/// let mut buffer = Vec::new();
+/// let mut writer = FramedWriter::new(&mut buffer);
+/// writer.write_message(&json!({ "key": "value" })).unwrap();
+/// assert_eq!(buffer, encode_message(&json!({ "key": "value" })).unwrap());
+/// ```
+pub struct FramedWriter<W: std::io::Write> {
+    writer: W,
+}
+
+impl<W: std::io::Write> FramedWriter<W> {
+    /// Creates a writer that frames messages onto `writer`.
+    pub fn new(writer: W) -> Self {
+        FramedWriter { writer }
+    }
+
+    /// Encodes a message, writes it and flushes the underlying writer.
+    ///
+    /// # Errors
+    /// Returns an `io::Error` if serialization, writing or flushing fails.
+    pub fn write_message<T: Serialize>(&mut self, message_content: &T) -> io::Result<()> {
+        encode_message_into(message_content, &mut self.writer)?;
+        self.writer.flush()
+    }
+}
+
 /// Asynchronously encodes a message and writes it to stdout according to the native messaging protocol.
 ///
 /// # Examples
@@ -112,6 +385,113 @@ where
     Ok(())
 }
 
+/// Asynchronously writes a `serde_json::Value` to stdout according to the native messaging protocol.
+///
+/// Behaves exactly like `send_message`, but the concrete parameter type avoids type inference
+/// problems in handlers that build replies dynamically.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::host::send_value;
+/// use serde_json::json;
+/// use tokio;
+///
+/// #[tokio::main()]
+/// async fn main() {
+///     if let Err(e) = send_value(&json!({ "ok": true })).await {
+///         eprintln!("Failed to send message: {}", e);
+///     }
+/// }
+/// ```
+///
+/// # Errors
+/// This function returns an `io::Error` if writing to stdout fails.
+pub async fn send_value(value: &serde_json::Value) -> io::Result<()> {
+    send_message(value).await
+}
+
+/// Asynchronously sends each element of `chunks` as its own message, tagging it with a
+/// sequential chunk id.
+///
+/// Browsers reject messages from the host larger than 1 MiB, so large datasets have to be
+/// split up. Each chunk must serialize to a JSON object; the zero-based index of the chunk
+/// is inserted into it under the `chunk_id_field` key before sending. Every chunk is
+/// serialized and checked against `MAX_TO_BROWSER` before the first one is sent, so a bad
+/// chunk never leaves a partial sequence behind.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::host::chunked_send;
+/// use serde_json::json;
+/// use tokio;
+///
+/// #[tokio::main()]
+/// async fn main() {
+///     let rows = vec![json!({ "rows": [1, 2, 3] }), json!({ "rows": [4, 5, 6] })];
+///     let sent = chunked_send(&rows, "chunk_id").await.expect("Failed to send chunks");
+///     assert_eq!(sent, 2);
+/// }
+/// ```
+///
+/// # Errors
+/// Returns an `io::Error` of kind `InvalidData` if a chunk does not serialize to a JSON object,
+/// or of kind `InvalidInput` if a chunk exceeds `MAX_TO_BROWSER`; nothing is sent in either
+/// case. If writing to stdout fails, the error message says how many chunks were already sent.
+pub async fn chunked_send<T>(chunks: &[T], chunk_id_field: &str) -> io::Result<usize>
+where
+    T: Serialize,
+{
+    let mut frames = Vec::with_capacity(chunks.len());
+    for (chunk_id, chunk) in chunks.iter().enumerate() {
+        let mut value = serde_json::to_value(chunk)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let object = value.as_object_mut().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("chunk {} is not a JSON object", chunk_id),
+            )
+        })?;
+        object.insert(chunk_id_field.to_string(), chunk_id.into());
+        let frame =
+            encode_message(&value).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let message_length = frame.len() - 4;
+        if message_length > MAX_TO_BROWSER {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "chunk {} is {} bytes, exceeding the maximum of {} bytes",
+                    chunk_id, message_length, MAX_TO_BROWSER
+                ),
+            ));
+        }
+        frames.push(frame);
+    }
+
+    let mut stdout = stdout();
+    for (sent, frame) in frames.iter().enumerate() {
+        let result = async {
+            stdout.write_all(frame).await?;
+            stdout.flush().await
+        }
+        .await;
+        result.map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!(
+                    "failed after sending {} of {} chunks: {}",
+                    sent,
+                    frames.len(),
+                    e
+                ),
+            )
+        })?;
+    }
+
+    Ok(frames.len())
+}
+
 /// Asynchronously runs the event loop, reading messages from stdin and handling them using a callback function.
 ///
 /// # Examples
@@ -156,3 +536,459 @@ where
         }
     }
 }
+
+/// A message read from stdin, carrying both its decoded JSON text and its raw bytes.
+///
+/// Handlers that forward messages to another transport can send `bytes` on as-is instead of
+/// re-encoding `json`. Cloning a `RawMessage` shares the byte buffer.
+#[derive(Debug, Clone)]
+pub struct RawMessage {
+    /// The UTF-8 JSON content of the message.
+    pub json: String,
+    /// The raw message content, without the length prefix.
+    pub bytes: Arc<[u8]>,
+    /// The length of the message content in bytes, as declared by the length prefix.
+    pub len: u32,
+}
+
+/// Asynchronously runs the event loop like `event_loop`, but passes each message to the
+/// callback as a `RawMessage`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::host::{event_loop_raw, RawMessage};
+/// use tokio;
+///
+/// #[tokio::main()]
+/// async fn main() {
+///     let mut total_bytes = 0u64;
+///     event_loop_raw(|message: RawMessage| {
+///         total_bytes += u64::from(message.len);
+///         eprintln!("Received {} bytes so far", total_bytes);
+///         async { Ok(()) }
+///     })
+///     .await;
+/// }
+/// ```
+///
+/// # Errors
+/// Prints an error message if reading from stdin fails or if the callback function returns an error.
+pub async fn event_loop_raw<F, Fut>(mut callback: F)
+where
+    F: FnMut(RawMessage) -> Fut,
+    Fut: std::future::Future<Output = io::Result<()>>,
+{
+    loop {
+        select! {
+            result = get_message_bytes() => {
+                let message = result.and_then(|content_bytes| {
+                    let json = std::str::from_utf8(&content_bytes)
+                        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+                        .to_string();
+                    Ok(RawMessage {
+                        json,
+                        len: content_bytes.len() as u32,
+                        bytes: content_bytes.into(),
+                    })
+                });
+                match message {
+                    Ok(message) => {
+                        if let Err(e) = callback(message).await {
+                            eprintln!("Failed to handle message: {}", e);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to read message: {}", e);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Checks that stdin is connected to a pipe rather than a terminal.
+///
+/// When a host is launched directly from a shell, `get_message` would block forever waiting
+/// for a length prefix that never arrives. Call this once at startup, before `event_loop`,
+/// to fail fast instead.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::host::assert_stdin_is_pipe;
+///
+/// if let Err(e) = assert_stdin_is_pipe() {
+///     eprintln!("{}", e);
+///     std::process::exit(1);
+/// }
+/// ```
+///
+/// # Errors
+/// Returns an `io::Error` if stdin is a terminal.
+pub fn assert_stdin_is_pipe() -> io::Result<()> {
+    if std::io::stdin().is_terminal() {
+        return Err(io::Error::other(
+            "stdin is a terminal; native messaging hosts must be launched by the browser",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Checks that stdout is connected to a pipe rather than a terminal.
+///
+/// Protocol frames start with a binary length prefix, which is garbage when printed to a
+/// terminal. Call this once at startup, before any message is sent, to catch accidental
+/// direct launches.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::host::{assert_stdin_is_pipe, assert_stdout_is_pipe};
+///
+/// if let Err(e) = assert_stdin_is_pipe().and_then(|_| assert_stdout_is_pipe()) {
+///     eprintln!("{}", e);
+///     std::process::exit(1);
+/// }
+/// ```
+///
+/// # Errors
+/// Returns an `io::Error` if stdout is a terminal.
+pub fn assert_stdout_is_pipe() -> io::Result<()> {
+    if std::io::stdout().is_terminal() {
+        return Err(io::Error::other(
+            "stdout is a terminal; native messaging hosts must be launched by the browser",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Asynchronously waits until the browser closes stdin.
+///
+/// The browser signals that the host should exit by closing its stdin. This future drains
+/// stdin on a background thread and resolves once end-of-file is reached, so it can be raced
+/// against other work with `tokio::select!`. Messages that arrive while it is being polled are
+/// discarded. Once the future is dropped the thread stops consuming input, so `get_message`
+/// can be used again after the `select!`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::host::watch_for_disconnect;
+/// use tokio;
+///
+/// async fn sync_bookmarks() {
+///     // Long-running work that should stop when the browser goes away.
+/// }
+///
+/// #[tokio::main()]
+/// async fn main() {
+///     tokio::select! {
+///         _ = watch_for_disconnect() => eprintln!("Browser disconnected"),
+///         _ = sync_bookmarks() => eprintln!("Sync finished"),
+///     }
+/// }
+/// ```
+///
+/// # Errors
+/// Returns an `io::Error` if reading from stdin fails.
+pub async fn watch_for_disconnect() -> io::Result<()> {
+    // Lives until this future completes or is dropped, then tells the thread to stop.
+    let watcher = DisconnectWatcher::default();
+    let cancelled = Arc::clone(&watcher.cancelled);
+    let (done_tx, done_rx) = tokio::sync::oneshot::channel();
+
+    // A plain thread rather than `spawn_blocking`, so a watcher still blocked on stdin never
+    // holds up runtime shutdown.
+    std::thread::spawn(move || {
+        let _ = done_tx.send(drain_stdin_until_eof(&cancelled));
+    });
+    done_rx.await.map_err(io::Error::other)?
+}
+
+/// Tells the stdin-draining thread to stop consuming input once `watch_for_disconnect` is
+/// dropped, such as when another `select!` branch wins.
+#[derive(Default)]
+struct DisconnectWatcher {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Drop for DisconnectWatcher {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+}
+
+fn drain_stdin_until_eof(cancelled: &AtomicBool) -> io::Result<()> {
+    // `tokio::io::stdin` reads through the same process-wide buffer, so anything left in it
+    // here is still seen by the next `get_message`.
+    let stdin = std::io::stdin();
+    loop {
+        let mut lock = stdin.lock();
+        let available = lock.fill_buf()?.len();
+        if available == 0 || cancelled.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        lock.consume(available);
+    }
+}
+
+/// A stream of messages read from an `AsyncRead` according to the native messaging protocol.
+///
+/// Each item is the UTF-8 JSON content of one message. The stream ends when the reader
+/// reaches end-of-file between messages. Requires the `stream` feature.
+///
+/// # Examples
+///
+/// ```
+/// use futures::StreamExt;
+/// use native_messaging::host::{encode_message, FramedStream};
+/// use serde_json::json;
+///
+/// #[tokio::main()]
+/// async fn main() {
+///     let bytes = encode_message(&json!({ "key": "value" })).unwrap();
+///     let mut stream = FramedStream::new(bytes.as_slice());
+///     assert_eq!(stream.next().await.unwrap().unwrap(), r#"{"key":"value"}"#);
+///     assert!(stream.next().await.is_none());
+/// }
+/// ```
+#[cfg(feature = "stream")]
+pub struct FramedStream<R> {
+    inner: tokio_util::codec::FramedRead<R, tokio_util::codec::LengthDelimitedCodec>,
+}
+
+#[cfg(feature = "stream")]
+fn framing_codec(max_size: usize) -> tokio_util::codec::LengthDelimitedCodec {
+    tokio_util::codec::LengthDelimitedCodec::builder()
+        .length_field_length(4)
+        .native_endian()
+        .max_frame_length(max_size)
+        .new_codec()
+}
+
+#[cfg(feature = "stream")]
+impl<R: io::AsyncRead> FramedStream<R> {
+    /// Creates a stream that reads messages from `reader`.
+    pub fn new(reader: R) -> Self {
+        FramedStream {
+            inner: tokio_util::codec::FramedRead::new(reader, framing_codec(MAX_FROM_BROWSER)),
+        }
+    }
+
+    /// Consumes the stream, returning the underlying reader.
+    ///
+    /// Any bytes already buffered from the reader but not yet returned as a message are lost.
+    pub fn into_inner(self) -> R {
+        self.inner.into_inner()
+    }
+}
+
+#[cfg(feature = "stream")]
+impl<R: io::AsyncRead + Unpin> futures_core::Stream for FramedStream<R> {
+    type Item = io::Result<String>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        std::pin::Pin::new(&mut self.inner)
+            .poll_next(cx)
+            .map(|frame| {
+                frame.map(|content_bytes| {
+                    String::from_utf8(content_bytes?.to_vec())
+                        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+                })
+            })
+    }
+}
+
+/// A sink that writes `serde_json::Value` messages to an `AsyncWrite` according to the native
+/// messaging protocol.
+///
+/// Messages larger than `MAX_TO_BROWSER` are rejected. Requires the `stream` feature.
+///
+/// # Examples
+///
+/// ```
+/// use futures::SinkExt;
+/// use native_messaging::host::{encode_message, FramedSink};
+/// use serde_json::json;
+///
+/// #[tokio::main()]
+/// async fn main() {
+///     let mut sink = FramedSink::new(Vec::new());
+///     sink.send(json!({ "key": "value" })).await.unwrap();
+///     assert_eq!(sink.into_inner(), encode_message(&json!({ "key": "value" })).unwrap());
+/// }
+/// ```
+#[cfg(feature = "stream")]
+pub struct FramedSink<W> {
+    inner: tokio_util::codec::FramedWrite<W, tokio_util::codec::LengthDelimitedCodec>,
+}
+
+#[cfg(feature = "stream")]
+impl<W: io::AsyncWrite> FramedSink<W> {
+    /// Creates a sink that writes messages to `writer`.
+    pub fn new(writer: W) -> Self {
+        FramedSink {
+            inner: tokio_util::codec::FramedWrite::new(writer, framing_codec(MAX_TO_BROWSER)),
+        }
+    }
+
+    /// Consumes the sink, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner.into_inner()
+    }
+}
+
+#[cfg(feature = "stream")]
+impl<W: io::AsyncWrite + Unpin> futures_sink::Sink<serde_json::Value> for FramedSink<W> {
+    type Error = io::Error;
+
+    fn poll_ready(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<io::Result<()>> {
+        futures_sink::Sink::<bytes::Bytes>::poll_ready(std::pin::Pin::new(&mut self.inner), cx)
+    }
+
+    fn start_send(
+        mut self: std::pin::Pin<&mut Self>,
+        message_content: serde_json::Value,
+    ) -> io::Result<()> {
+        let encoded_content = serde_json::to_vec(&message_content)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        std::pin::Pin::new(&mut self.inner).start_send(bytes::Bytes::from(encoded_content))
+    }
+
+    fn poll_flush(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<io::Result<()>> {
+        futures_sink::Sink::<bytes::Bytes>::poll_flush(std::pin::Pin::new(&mut self.inner), cx)
+    }
+
+    fn poll_close(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<io::Result<()>> {
+        futures_sink::Sink::<bytes::Bytes>::poll_close(std::pin::Pin::new(&mut self.inner), cx)
+    }
+}
+
+/// Returns the process's synchronous stdin and stdout, for use with the synchronous framing
+/// functions such as `read_message_header` and `read_message_body`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::host::{
+///     encode_message, read_message_body, read_message_header, split_stdin_stdout_sync,
+///     MAX_FROM_BROWSER,
+/// };
+/// use serde_json::Value;
+/// use std::io::Write;
+///
+/// let (mut reader, mut writer) = split_stdin_stdout_sync();
+/// let length = read_message_header(&mut reader).expect("Failed to read header");
+/// let message = read_message_body(&mut reader, length, MAX_FROM_BROWSER).expect("Failed to read body");
+/// let value: Value = serde_json::from_str(&message).expect("Invalid JSON");
+/// writer.write_all(&encode_message(&value).unwrap()).expect("Failed to write reply");
+/// writer.flush().expect("Failed to flush reply");
+/// ```
+pub fn split_stdin_stdout_sync() -> (impl Read, impl std::io::Write) {
+    (std::io::stdin(), std::io::stdout())
+}
+
+/// A native messaging host running as a child process, driven the way a browser drives it.
+///
+/// Created with `spawn_host_process`. Useful for integration testing a host binary. If it is
+/// dropped without calling `wait`, such as when a test panics, the host is killed and reaped
+/// so no process is leaked.
+pub struct HostProcess {
+    child: std::process::Child,
+    // `None` once `wait` has closed it.
+    stdin: Option<std::io::BufWriter<std::process::ChildStdin>>,
+    stdout: std::io::BufReader<std::process::ChildStdout>,
+}
+
+impl HostProcess {
+    /// Encodes a message and writes it to the host's stdin.
+    ///
+    /// # Errors
+    /// Returns an `io::Error` if serialization or writing fails.
+    pub fn send<T: Serialize>(&mut self, message_content: &T) -> io::Result<()> {
+        use std::io::Write;
+
+        let stdin = self
+            .stdin
+            .as_mut()
+            .ok_or_else(|| io::Error::from(io::ErrorKind::BrokenPipe))?;
+        encode_message_into(message_content, stdin)?;
+        stdin.flush()
+    }
+
+    /// Reads the next message from the host's stdout and deserializes it.
+    ///
+    /// # Errors
+    /// Returns an `io::Error` if reading fails, if the message exceeds `MAX_TO_BROWSER`, or
+    /// if it cannot be deserialized into `T`.
+    pub fn recv<T: DeserializeOwned>(&mut self) -> io::Result<T> {
+        let length = read_message_header(&mut self.stdout)?;
+        let message = read_message_body(&mut self.stdout, length, MAX_TO_BROWSER)?;
+        Ok(serde_json::from_str(&message)?)
+    }
+
+    /// Closes the host's stdin, as a browser does on disconnect, and waits for it to exit.
+    ///
+    /// # Errors
+    /// Returns an `io::Error` if waiting for the process fails.
+    pub fn wait(mut self) -> io::Result<std::process::ExitStatus> {
+        drop(self.stdin.take());
+        self.child.wait()
+    }
+}
+
+impl Drop for HostProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Spawns a native messaging host with piped stdin and stdout.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::host::spawn_host_process;
+/// use serde_json::{json, Value};
+///
+/// let mut host = spawn_host_process("target/debug/my_host", &[]).expect("Failed to spawn host");
+/// host.send(&json!({ "ping": 1 })).expect("Failed to send message");
+/// let reply: Value = host.recv().expect("Failed to receive reply");
+/// println!("Host replied: {}", reply);
+/// assert!(host.wait().expect("Failed to wait for host").success());
+/// ```
+///
+/// # Errors
+/// Returns an `io::Error` if the process cannot be spawned.
+pub fn spawn_host_process(cmd: &str, args: &[&str]) -> io::Result<HostProcess> {
+    let mut child = std::process::Command::new(cmd)
+        .args(args)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()?;
+    let stdin = child.stdin.take().expect("stdin is piped");
+    let stdout = child.stdout.take().expect("stdout is piped");
+
+    Ok(HostProcess {
+        child,
+        stdin: Some(std::io::BufWriter::new(stdin)),
+        stdout: std::io::BufReader::new(stdout),
+    })
+}
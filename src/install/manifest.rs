@@ -1,34 +1,375 @@
-use serde::{Deserialize, Serialize};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
 use std::{
     collections::HashMap,
     env,
     fs::{self, File},
     io::{self, Write},
-    path::PathBuf,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 /// Stores information about browser-specific paths and registries for native messaging.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct BrowserInfo {
     pub registry: Option<String>,
     pub linux: Option<PathBuf>,
     pub darwin: Option<PathBuf>,
+    /// Manifest file name for browsers that do not use `{host_name}.json`. Every occurrence
+    /// of `{host_name}` is replaced with the host name.
+    #[serde(default)]
+    pub filename_template: Option<String>,
+}
+
+impl BrowserInfo {
+    /// Returns the native messaging hosts directory for the operating system this crate was
+    /// compiled for, or `None` if the browser has no directory on that OS.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use native_messaging::install::manifest::get_browser_info;
+    ///
+    /// let browser_info = get_browser_info();
+    /// if let Some(manifest_path) = browser_info["firefox"].for_current_os() {
+    ///     println!("Firefox manifests live in {}", manifest_path.display());
+    /// }
+    /// ```
+    pub fn for_current_os(&self) -> Option<&PathBuf> {
+        if cfg!(target_os = "macos") {
+            self.darwin.as_ref()
+        } else if cfg!(target_os = "linux") {
+            self.linux.as_ref()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the file name of the manifest for `host_name` inside this browser's
+    /// native messaging hosts directory.
+    ///
+    /// Uses `filename_template` when set and `{host_name}.json` otherwise. Every browser
+    /// returned by `get_browser_info` currently uses the default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use native_messaging::install::manifest::{get_browser_info, BrowserInfo};
+    ///
+    /// let browser_info = get_browser_info();
+    /// assert_eq!(browser_info["chrome"].manifest_filename_for("my_extension"), "my_extension.json");
+    ///
+    /// let custom = BrowserInfo {
+    ///     filename_template: Some("{host_name}.manifest.json".to_string()),
+    ///     ..BrowserInfo::default()
+    /// };
+    /// assert_eq!(custom.manifest_filename_for("my_extension"), "my_extension.manifest.json");
+    /// ```
+    pub fn manifest_filename_for(&self, host_name: &str) -> String {
+        match &self.filename_template {
+            Some(template) => template.replace("{host_name}", host_name),
+            None => format!("{}.json", host_name),
+        }
+    }
 }
 
 /// Represents a native messaging manifest.
-#[derive(Serialize, Deserialize, Debug)]
+///
+/// Serializing a manifest always emits `"type": "stdio"`, the only transport browsers
+/// support, so it is not stored as a field.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Manifest {
     pub name: String,
     pub description: String,
     pub path: PathBuf,
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub allowed_origins: Option<Vec<String>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub allowed_extensions: Option<Vec<String>>,
 }
 
+impl Serialize for Manifest {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Manifest", 6)?;
+        state.serialize_field("name", &self.name)?;
+        state.serialize_field("description", &self.description)?;
+        state.serialize_field("path", &self.path)?;
+        state.serialize_field("type", "stdio")?;
+        match &self.allowed_origins {
+            Some(origins) => state.serialize_field("allowed_origins", origins)?,
+            None => state.skip_field("allowed_origins")?,
+        }
+        match &self.allowed_extensions {
+            Some(extensions) => state.serialize_field("allowed_extensions", extensions)?,
+            None => state.skip_field("allowed_extensions")?,
+        }
+        state.end()
+    }
+}
+
+impl Manifest {
+    /// Returns a builder for assembling a manifest field by field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use native_messaging::install::manifest::Manifest;
+    ///
+    /// let manifest = Manifest::builder()
+    ///     .name("my_extension")
+    ///     .description("An example extension")
+    ///     .path("/opt/my_extension/host")
+    ///     .allowed_extensions(vec!["my_extension@example.org".to_string()])
+    ///     .build()
+    ///     .expect("Invalid manifest");
+    /// assert_eq!(manifest.name, "my_extension");
+    /// ```
+    pub fn builder() -> ManifestBuilder {
+        ManifestBuilder::default()
+    }
+
+    /// Returns the host name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the description shown by the browser.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Returns the path to the host executable.
+    pub fn exe_path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns a copy of the manifest pointing at a different host executable.
+    ///
+    /// As in `install`, the new path is canonicalized, so it must exist.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use native_messaging::install::manifest::{install_from_struct, Manifest};
+    /// use std::path::Path;
+    ///
+    /// let manifest = Manifest::builder()
+    ///     .name("my_extension")
+    ///     .description("An example extension")
+    ///     .path("/opt/my_extension/host")
+    ///     .build()
+    ///     .expect("Invalid manifest");
+    /// let moved = manifest
+    ///     .with_exe_path(Path::new("/usr/lib/my_extension/host"))
+    ///     .expect("Executable not found");
+    /// install_from_struct("chrome", &moved).expect("Failed to install extension");
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an `io::Error` if `new_path` cannot be canonicalized.
+    pub fn with_exe_path(&self, new_path: &Path) -> io::Result<Manifest> {
+        Ok(Manifest {
+            path: fs::canonicalize(new_path)?,
+            ..self.clone()
+        })
+    }
+
+    /// Compares two manifests field by field, ignoring the executable path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use native_messaging::install::manifest::Manifest;
+    ///
+    /// let manifest = Manifest::builder()
+    ///     .name("my_extension")
+    ///     .description("An example extension")
+    ///     .path("/opt/my_extension/host")
+    ///     .build()
+    ///     .expect("Invalid manifest");
+    /// let moved = Manifest {
+    ///     path: "/usr/lib/my_extension/host".into(),
+    ///     ..manifest.clone()
+    /// };
+    /// assert!(manifest.eq_ignoring_path(&moved));
+    /// assert_ne!(manifest, moved);
+    /// ```
+    pub fn eq_ignoring_path(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.description == other.description
+            && self.allowed_origins == other.allowed_origins
+            && self.allowed_extensions == other.allowed_extensions
+    }
+
+    /// Returns the extensions allowed to connect to the host: the Chrome `allowed_origins`
+    /// followed by the Firefox `allowed_extensions`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use native_messaging::install::manifest::Manifest;
+    ///
+    /// let manifest = Manifest::builder()
+    ///     .name("my_extension")
+    ///     .description("An example extension")
+    ///     .path("/opt/my_extension/host")
+    ///     .allowed_extensions(vec!["my_extension@example.org".to_string()])
+    ///     .build()
+    ///     .expect("Invalid manifest");
+    /// assert_eq!(manifest.allowed_ids(), vec!["my_extension@example.org"]);
+    /// ```
+    pub fn allowed_ids(&self) -> Vec<&str> {
+        self.allowed_origins
+            .iter()
+            .chain(self.allowed_extensions.iter())
+            .flatten()
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Serializes the manifest into compact JSON.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use native_messaging::install::manifest::Manifest;
+    /// use std::path::PathBuf;
+    ///
+    /// let manifest = Manifest {
+    ///     name: "my_extension".to_string(),
+    ///     description: "An example extension".to_string(),
+    ///     path: PathBuf::from("/opt/my_extension/host"),
+    ///     allowed_origins: None,
+    ///     allowed_extensions: None,
+    /// };
+    /// let json = manifest.to_json_string().expect("Serialization failed");
+    /// assert!(json.starts_with(r#"{"name":"my_extension""#));
+    /// ```
+    pub fn to_json_string(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Serializes the manifest into pretty-printed JSON, as written by `install`.
+    pub fn to_json_string_pretty(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Writes the manifest as pretty-printed JSON directly to `w`, without building an
+    /// intermediate `String`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use native_messaging::install::manifest::Manifest;
+    ///
+    /// let manifest = Manifest::builder()
+    ///     .name("my_extension")
+    ///     .description("An example extension")
+    ///     .path("/opt/my_extension/host")
+    ///     .build()
+    ///     .expect("Invalid manifest");
+    /// let mut buffer = Vec::new();
+    /// manifest.to_writer(&mut buffer).expect("Serialization failed");
+    /// assert_eq!(buffer, manifest.to_json_string_pretty().unwrap().into_bytes());
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an `io::Error` if serialization or writing fails.
+    pub fn to_writer<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        serde_json::to_writer_pretty(w, self)?;
+        Ok(())
+    }
+}
+
+/// Builds a `Manifest`, validating its fields on `build`.
+///
+/// Created with `Manifest::builder`.
+#[must_use]
+#[derive(Debug, Default, Clone)]
+pub struct ManifestBuilder {
+    name: Option<String>,
+    description: Option<String>,
+    path: Option<PathBuf>,
+    allowed_origins: Option<Vec<String>>,
+    allowed_extensions: Option<Vec<String>>,
+}
+
+impl ManifestBuilder {
+    /// Sets the host name, which must match the manifest file name.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the description shown by the browser.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the path to the host executable.
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Sets the Chrome extension origins allowed to connect to the host.
+    pub fn allowed_origins(mut self, allowed_origins: Vec<String>) -> Self {
+        self.allowed_origins = Some(allowed_origins);
+        self
+    }
+
+    /// Sets the Firefox extension IDs allowed to connect to the host.
+    pub fn allowed_extensions(mut self, allowed_extensions: Vec<String>) -> Self {
+        self.allowed_extensions = Some(allowed_extensions);
+        self
+    }
+
+    /// Validates the fields and builds the `Manifest`.
+    ///
+    /// # Errors
+    /// Returns an `io::Error` of kind `InvalidInput` if the name, description or path is
+    /// missing, or if the name is not a valid host name. Valid names consist of lowercase
+    /// ASCII letters, digits and underscores, optionally separated by single dots.
+    pub fn build(self) -> io::Result<Manifest> {
+        let missing = |field: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Manifest field is missing: {}", field),
+            )
+        };
+        let name = self.name.ok_or_else(|| missing("name"))?;
+        let description = self.description.ok_or_else(|| missing("description"))?;
+        let path = self.path.ok_or_else(|| missing("path"))?;
+
+        check_host_name(&name)?;
+
+        Ok(Manifest {
+            name,
+            description,
+            path,
+            allowed_origins: self.allowed_origins,
+            allowed_extensions: self.allowed_extensions,
+        })
+    }
+}
+
+/// Records when and by which version of this crate a manifest was installed.
+///
+/// Written by `install_with_metadata` as a `{name}.nm-meta.json` sidecar file next to
+/// the manifest.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct InstallMetadata {
+    /// Installation time in seconds since the Unix epoch.
+    pub installed_at: u64,
+    /// Version of this crate that wrote the manifest.
+    pub crate_version: String,
+}
+
 /// Gets information about supported browsers, such as paths for native messaging hosts.
 ///
+/// Supported browsers are `chrome`, `chromium`, `edge`, `brave` and `vivaldi` from the
+/// Chromium family, and `firefox` and `librewolf` from the Firefox family.
+///
 /// # Examples
 ///
 /// ```no_run
@@ -54,6 +395,7 @@ pub fn get_browser_info() -> HashMap<String, BrowserInfo> {
                 "{}/Library/Application Support/Google/Chrome/NativeMessagingHosts",
                 home_dir
             ))),
+            filename_template: None,
         },
     );
 
@@ -69,6 +411,89 @@ pub fn get_browser_info() -> HashMap<String, BrowserInfo> {
                 "{}/Library/Application Support/Mozilla/NativeMessagingHosts",
                 home_dir
             ))),
+            filename_template: None,
+        },
+    );
+
+    browser_info.insert(
+        "chromium".to_string(),
+        BrowserInfo {
+            registry: Some("Software\\Chromium\\NativeMessagingHosts".to_string()),
+            linux: Some(PathBuf::from(format!(
+                "{}/.config/chromium/NativeMessagingHosts",
+                home_dir
+            ))),
+            darwin: Some(PathBuf::from(format!(
+                "{}/Library/Application Support/Chromium/NativeMessagingHosts",
+                home_dir
+            ))),
+            filename_template: None,
+        },
+    );
+
+    browser_info.insert(
+        "edge".to_string(),
+        BrowserInfo {
+            registry: Some("Software\\Microsoft\\Edge\\NativeMessagingHosts".to_string()),
+            linux: Some(PathBuf::from(format!(
+                "{}/.config/microsoft-edge/NativeMessagingHosts",
+                home_dir
+            ))),
+            darwin: Some(PathBuf::from(format!(
+                "{}/Library/Application Support/Microsoft Edge/NativeMessagingHosts",
+                home_dir
+            ))),
+            filename_template: None,
+        },
+    );
+
+    browser_info.insert(
+        "brave".to_string(),
+        BrowserInfo {
+            registry: Some(
+                "Software\\BraveSoftware\\Brave-Browser\\NativeMessagingHosts".to_string(),
+            ),
+            linux: Some(PathBuf::from(format!(
+                "{}/.config/BraveSoftware/Brave-Browser/NativeMessagingHosts",
+                home_dir
+            ))),
+            darwin: Some(PathBuf::from(format!(
+                "{}/Library/Application Support/BraveSoftware/Brave-Browser/NativeMessagingHosts",
+                home_dir
+            ))),
+            filename_template: None,
+        },
+    );
+
+    browser_info.insert(
+        "vivaldi".to_string(),
+        BrowserInfo {
+            registry: Some("Software\\Vivaldi\\NativeMessagingHosts".to_string()),
+            linux: Some(PathBuf::from(format!(
+                "{}/.config/vivaldi/NativeMessagingHosts",
+                home_dir
+            ))),
+            darwin: Some(PathBuf::from(format!(
+                "{}/Library/Application Support/Vivaldi/NativeMessagingHosts",
+                home_dir
+            ))),
+            filename_template: None,
+        },
+    );
+
+    browser_info.insert(
+        "librewolf".to_string(),
+        BrowserInfo {
+            registry: Some("Software\\LibreWolf\\NativeMessagingHosts".to_string()),
+            linux: Some(PathBuf::from(format!(
+                "{}/.librewolf/native-messaging-hosts",
+                home_dir
+            ))),
+            darwin: Some(PathBuf::from(format!(
+                "{}/Library/Application Support/LibreWolf/NativeMessagingHosts",
+                home_dir
+            ))),
+            filename_template: None,
         },
     );
 
@@ -80,29 +505,86 @@ fn write_file(filename: &PathBuf, contents: &str) -> io::Result<()> {
     file.write_all(contents.as_bytes())
 }
 
-fn write_manifest(browser: &str, path: &PathBuf, manifest: &mut Manifest) -> io::Result<()> {
-    match browser {
-        "firefox" => manifest.allowed_origins = None,
-        "chrome" => manifest.allowed_extensions = None,
-        _ => {}
+fn is_chromium_family(browser: &str) -> bool {
+    matches!(
+        browser,
+        "chrome" | "chromium" | "edge" | "brave" | "vivaldi"
+    )
+}
+
+fn is_firefox_family(browser: &str) -> bool {
+    matches!(browser, "firefox" | "librewolf")
+}
+
+fn check_host_name(name: &str) -> io::Result<()> {
+    let is_valid_name = name.split('.').all(|segment| {
+        !segment.is_empty()
+            && segment
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    });
+    if !is_valid_name {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Invalid host name: {}", name),
+        ));
     }
+    Ok(())
+}
 
-    let manifest_json = serde_json::to_string_pretty(manifest).map_err(|e| {
-        io::Error::new(io::ErrorKind::Other, format!("Serialization failed: {}", e))
-    })?;
+fn manifest_for_browser(browser: &str, manifest: &Manifest) -> Manifest {
+    let mut manifest = manifest.clone();
+    // Browsers expect the allow list for their family to be present, even when empty.
+    if is_firefox_family(browser) {
+        manifest.allowed_origins = None;
+        manifest.allowed_extensions.get_or_insert_with(Vec::new);
+    } else if is_chromium_family(browser) {
+        manifest.allowed_extensions = None;
+        manifest.allowed_origins.get_or_insert_with(Vec::new);
+    }
+    manifest
+}
+
+fn manifest_json(browser: &str, manifest: &Manifest) -> io::Result<String> {
+    manifest_for_browser(browser, manifest)
+        .to_json_string_pretty()
+        .map_err(|e| io::Error::other(format!("Serialization failed: {}", e)))
+}
+
+fn write_manifest(browser: &str, path: &PathBuf, manifest: &Manifest) -> io::Result<()> {
+    check_allowed_ids(browser, manifest)?;
+    let manifest_json = manifest_json(browser, manifest)?;
     write_file(path, &manifest_json)
 }
 
-fn install_unix(browsers: &[&str], manifest: &mut Manifest) -> io::Result<()> {
+fn manifest_file(name: &str, browser: &str) -> io::Result<PathBuf> {
+    let browser_info = get_browser_info();
+    let unsupported = || {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Unsupported browser: {}", browser),
+        )
+    };
+    let info = browser_info.get(browser).ok_or_else(unsupported)?;
+    let manifest_path = info.for_current_os().ok_or_else(unsupported)?;
+    Ok(manifest_path.join(info.manifest_filename_for(name)))
+}
+
+fn install_unix(
+    browsers: &[&str],
+    manifest: &Manifest,
+    on_progress: &dyn Fn(&str, &Path),
+) -> io::Result<()> {
     let browser_info = get_browser_info();
     for &browser in browsers {
         if let Some(info) = browser_info.get(browser) {
-            if let Some(manifest_path) = &info.linux {
+            if let Some(manifest_path) = info.for_current_os() {
                 if !manifest_path.exists() {
                     fs::create_dir_all(manifest_path)?;
                 }
-                let manifest_file = manifest_path.join(format!("{}.json", manifest.name));
+                let manifest_file = manifest_path.join(info.manifest_filename_for(&manifest.name));
                 write_manifest(browser, &manifest_file, manifest)?;
+                on_progress(browser, &manifest_file);
             }
         }
     }
@@ -119,17 +601,206 @@ fn install_unix(browsers: &[&str], manifest: &mut Manifest) -> io::Result<()> {
 /// install("my_extension", "An example extension", "/path/to/extension", &["chrome", "firefox"])
 ///     .expect("Failed to install extension");
 /// ```
+///
+/// # Errors
+/// Returns an `io::Error` of kind `InvalidInput` if `name` is not a valid host name: only
+/// lowercase ASCII letters, digits and underscores, optionally separated by single dots, are
+/// allowed, so a name such as `my-extension` is rejected. Also returns an error if `path`
+/// cannot be canonicalized or a manifest cannot be written.
 pub fn install(name: &str, description: &str, path: &str, browsers: &[&str]) -> io::Result<()> {
-    let manifest = Manifest {
-        name: name.to_string(),
-        description: description.to_string(),
-        path: PathBuf::from(path),
-        allowed_origins: None,
-        allowed_extensions: None,
-    };
-    let mut manifest = manifest;
-    manifest.path = fs::canonicalize(&manifest.path)?;
-    install_unix(browsers, &mut manifest)
+    let manifest = new_manifest(name, description, path)?;
+    install_unix(browsers, &manifest, &|_, _| {})
+}
+
+/// Installs the manifest file for the given browsers, like `install`, calling `on_progress`
+/// with the browser key and the written path after each manifest is written.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::install::manifest::install_with_progress;
+///
+/// install_with_progress(
+///     "my_extension",
+///     "An example extension",
+///     "/path/to/extension",
+///     &["chrome", "firefox"],
+///     &|browser, path| println!("✓ {} → {}", browser, path.display()),
+/// )
+/// .expect("Failed to install extension");
+/// ```
+pub fn install_with_progress(
+    name: &str,
+    description: &str,
+    path: &str,
+    browsers: &[&str],
+    on_progress: &dyn Fn(&str, &Path),
+) -> io::Result<()> {
+    let manifest = new_manifest(name, description, path)?;
+    install_unix(browsers, &manifest, on_progress)
+}
+
+fn new_manifest(name: &str, description: &str, path: &str) -> io::Result<Manifest> {
+    Manifest::builder()
+        .name(name)
+        .description(description)
+        .path(fs::canonicalize(path)?)
+        .build()
+}
+
+fn check_browser_family(
+    browsers: &[&str],
+    is_family: fn(&str) -> bool,
+    family: &str,
+) -> io::Result<()> {
+    match browsers.iter().find(|&&browser| !is_family(browser)) {
+        Some(browser) => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is not a {} browser", browser, family),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Installs the manifest file for Chromium-based browsers, allowing the given extension
+/// origins to connect.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::install::manifest::install_chromium_only;
+///
+/// install_chromium_only(
+///     "my_extension",
+///     "An example extension",
+///     "/path/to/extension",
+///     &["chrome-extension://knldjmfmopnpolahpmmgbagdohdnhkik/".to_string()],
+///     &["chrome"],
+/// )
+/// .expect("Failed to install extension");
+/// ```
+///
+/// # Errors
+/// Returns an `io::Error` of kind `InvalidInput` if any browser is not Chromium-based or any
+/// origin is malformed, or any error from writing the manifests.
+pub fn install_chromium_only(
+    name: &str,
+    description: &str,
+    path: &str,
+    origins: &[String],
+    browsers: &[&str],
+) -> io::Result<()> {
+    check_browser_family(browsers, is_chromium_family, "Chromium-based")?;
+    let mut manifest = new_manifest(name, description, path)?;
+    manifest.allowed_origins = Some(origins.to_vec());
+    install_unix(browsers, &manifest, &|_, _| {})
+}
+
+/// Installs the manifest file for Firefox-based browsers, allowing the given extension IDs
+/// to connect.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::install::manifest::install_firefox_only;
+///
+/// install_firefox_only(
+///     "my_extension",
+///     "An example extension",
+///     "/path/to/extension",
+///     &["my_extension@example.org".to_string()],
+///     &["firefox"],
+/// )
+/// .expect("Failed to install extension");
+/// ```
+///
+/// # Errors
+/// Returns an `io::Error` of kind `InvalidInput` if any browser is not Firefox-based or any
+/// extension ID is malformed, or any error from writing the manifests.
+pub fn install_firefox_only(
+    name: &str,
+    description: &str,
+    path: &str,
+    extensions: &[String],
+    browsers: &[&str],
+) -> io::Result<()> {
+    check_browser_family(browsers, is_firefox_family, "Firefox-based")?;
+    let mut manifest = new_manifest(name, description, path)?;
+    manifest.allowed_extensions = Some(extensions.to_vec());
+    install_unix(browsers, &manifest, &|_, _| {})
+}
+
+/// Estimates the number of bytes `install` would write for the given browsers, without
+/// writing anything.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::install::manifest::compute_install_size;
+///
+/// let size = compute_install_size(
+///     "my_extension",
+///     "An example extension",
+///     "/path/to/extension",
+///     &["chrome", "firefox"],
+/// )
+/// .expect("Failed to compute install size");
+/// println!("install will write {} bytes", size);
+/// ```
+pub fn compute_install_size(
+    name: &str,
+    description: &str,
+    path: &str,
+    browsers: &[&str],
+) -> io::Result<u64> {
+    let manifest = new_manifest(name, description, path)?;
+    let browser_info = get_browser_info();
+    let mut total_size = 0;
+    for &browser in browsers {
+        if let Some(info) = browser_info.get(browser) {
+            if info.for_current_os().is_some() {
+                total_size += manifest_json(browser, &manifest)?.len() as u64;
+            }
+        }
+    }
+    Ok(total_size)
+}
+
+/// Installs a caller-assembled manifest for a single browser.
+///
+/// Unlike `install`, the executable path is not canonicalized and no browser-specific fields
+/// are stripped. The host name is validated as in `ManifestBuilder::build`, and allowed IDs
+/// must match the ID format of `browser`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::install::manifest::{install_from_struct, Manifest};
+/// use std::path::PathBuf;
+///
+/// let manifest = Manifest {
+///     name: "my_extension".to_string(),
+///     description: "An example extension".to_string(),
+///     path: PathBuf::from("/opt/my_extension/host"),
+///     allowed_origins: Some(vec!["chrome-extension://abcdefghijklmnopabcdefghijklmnop/".to_string()]),
+///     allowed_extensions: None,
+/// };
+/// install_from_struct("chrome", &manifest).expect("Failed to install extension");
+/// ```
+///
+/// # Errors
+/// Returns an `io::Error` of kind `InvalidInput` if the host name is invalid or an allowed ID
+/// has the wrong format for `browser`, of kind `NotFound` if the browser is unsupported, or
+/// any error from writing the file.
+pub fn install_from_struct(browser: &str, manifest: &Manifest) -> io::Result<()> {
+    check_host_name(&manifest.name)?;
+    check_allowed_ids(browser, manifest)?;
+    let manifest_file = manifest_file(&manifest.name, browser)?;
+    if let Some(manifest_path) = manifest_file.parent() {
+        fs::create_dir_all(manifest_path)?;
+    }
+
+    manifest.to_writer(&mut File::create(manifest_file)?)
 }
 
 /// Verifies if the manifest file is installed for the specified browsers.
@@ -148,9 +819,9 @@ pub fn install(name: &str, description: &str, path: &str, browsers: &[&str]) ->
 /// ```
 pub fn verify(name: &str) -> io::Result<bool> {
     let browser_info = get_browser_info();
-    for (_, info) in &browser_info {
-        if let Some(manifest_path) = &info.linux {
-            let manifest_file = manifest_path.join(format!("{}.json", name));
+    for info in browser_info.values() {
+        if let Some(manifest_path) = info.for_current_os() {
+            let manifest_file = manifest_path.join(info.manifest_filename_for(name));
             if manifest_file.exists() {
                 return Ok(true);
             }
@@ -159,6 +830,29 @@ pub fn verify(name: &str) -> io::Result<bool> {
     Ok(false)
 }
 
+/// Verifies if the manifest file is installed in a custom directory, such as a Docker
+/// volume or CI artifact, rather than in a browser's own directory.
+///
+/// No browser is given, so the default `{name}.json` file name is looked for.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::install::manifest::verify_in;
+/// use std::path::Path;
+///
+/// let is_installed = verify_in("my_extension", Path::new("/mnt/artifacts/manifests"))
+///     .expect("Verification failed");
+/// if is_installed {
+///     println!("Manifest is installed.");
+/// }
+/// ```
+pub fn verify_in(name: &str, base_dir: &Path) -> io::Result<bool> {
+    base_dir
+        .join(BrowserInfo::default().manifest_filename_for(name))
+        .try_exists()
+}
+
 /// Removes the manifest file for specified browsers.
 ///
 /// # Examples
@@ -169,16 +863,814 @@ pub fn verify(name: &str) -> io::Result<bool> {
 /// remove("my_extension", &["chrome", "firefox"]).expect("Failed to remove extension");
 /// ```
 pub fn remove(name: &str, browsers: &[&str]) -> io::Result<()> {
-    let browser_info = get_browser_info();
     for &browser in browsers {
-        if let Some(info) = browser_info.get(browser) {
-            if let Some(manifest_path) = &info.linux {
-                let manifest_file = manifest_path.join(format!("{}.json", name));
-                if manifest_file.exists() {
-                    fs::remove_file(manifest_file)?;
+        if let Ok(manifest_file) = manifest_file(name, browser) {
+            if manifest_file.exists() {
+                fs::remove_file(manifest_file)?;
+            }
+        }
+        if let Ok(metadata_file) = metadata_file(name, browser) {
+            if metadata_file.exists() {
+                fs::remove_file(metadata_file)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Computes the SHA-256 digest of the installed manifest file for a browser.
+///
+/// The digest is returned as a lowercase hex string, which is handy for change detection in
+/// CI pipelines and for signing workflows. Requires the `checksum` feature.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::install::manifest::checksum_manifest;
+///
+/// let digest = checksum_manifest("my_extension", "chrome").expect("Failed to hash manifest");
+/// println!("chrome manifest sha256: {}", digest);
+/// ```
+#[cfg(feature = "checksum")]
+pub fn checksum_manifest(name: &str, browser: &str) -> io::Result<String> {
+    use sha2::{Digest, Sha256};
+
+    let contents = fs::read(manifest_file(name, browser)?)?;
+    let digest = Sha256::digest(&contents);
+    Ok(digest.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// Checks whether the installed manifest file for a browser matches an expected SHA-256
+/// digest. Requires the `checksum` feature.
+///
+/// The comparison ignores the case of `expected_hex`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::install::manifest::compare_checksum;
+///
+/// let expected = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+/// if !compare_checksum("my_extension", "chrome", expected).expect("Failed to hash manifest") {
+///     eprintln!("chrome manifest was modified");
+/// }
+/// ```
+#[cfg(feature = "checksum")]
+pub fn compare_checksum(name: &str, browser: &str, expected_hex: &str) -> io::Result<bool> {
+    let digest = checksum_manifest(name, browser)?;
+    Ok(digest.eq_ignore_ascii_case(expected_hex))
+}
+
+/// Fields that make up the manifest itself and must not be changed as custom fields.
+const RESERVED_FIELDS: &[&str] = &[
+    "name",
+    "type",
+    "path",
+    "allowed_origins",
+    "allowed_extensions",
+];
+
+fn check_custom_field(key: &str) -> io::Result<()> {
+    if RESERVED_FIELDS.contains(&key) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Cannot modify required manifest field: {}", key),
+        ));
+    }
+    Ok(())
+}
+
+fn read_manifest_object(
+    manifest_file: &PathBuf,
+) -> io::Result<serde_json::Map<String, serde_json::Value>> {
+    let contents = fs::read_to_string(manifest_file)?;
+    match serde_json::from_str(&contents)? {
+        serde_json::Value::Object(object) => Ok(object),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Manifest is not a JSON object: {}", manifest_file.display()),
+        )),
+    }
+}
+
+fn write_manifest_object(
+    manifest_file: &PathBuf,
+    object: serde_json::Map<String, serde_json::Value>,
+) -> io::Result<()> {
+    let manifest_json = serde_json::to_string_pretty(&serde_json::Value::Object(object))
+        .map_err(|e| io::Error::other(format!("Serialization failed: {}", e)))?;
+    write_file(manifest_file, &manifest_json)
+}
+
+/// Inserts or replaces a custom field in an installed manifest.
+///
+/// Some browser betas and custom builds accept extra manifest keys. The fields that define
+/// the manifest itself (`name`, `type`, `path`, `allowed_origins` and `allowed_extensions`)
+/// cannot be set this way.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::install::manifest::add_custom_field;
+/// use serde_json::json;
+///
+/// add_custom_field("my_extension", "chrome", "beta_flag", json!(true))
+///     .expect("Failed to update manifest");
+/// ```
+pub fn add_custom_field(
+    name: &str,
+    browser: &str,
+    key: &str,
+    value: serde_json::Value,
+) -> io::Result<()> {
+    check_custom_field(key)?;
+    let manifest_file = manifest_file(name, browser)?;
+    let mut object = read_manifest_object(&manifest_file)?;
+    object.insert(key.to_string(), value);
+    write_manifest_object(&manifest_file, object)
+}
+
+/// Removes a custom field from an installed manifest.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::install::manifest::remove_custom_field;
+///
+/// remove_custom_field("my_extension", "chrome", "beta_flag").expect("Failed to update manifest");
+/// ```
+///
+/// # Errors
+/// Returns an `io::Error` of kind `NotFound` if the field is not present, or `InvalidInput`
+/// if `key` is one of the required manifest fields.
+pub fn remove_custom_field(name: &str, browser: &str, key: &str) -> io::Result<()> {
+    check_custom_field(key)?;
+    let manifest_file = manifest_file(name, browser)?;
+    let mut object = read_manifest_object(&manifest_file)?;
+    if object.remove(key).is_none() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Field not found in manifest: {}", key),
+        ));
+    }
+    write_manifest_object(&manifest_file, object)
+}
+
+fn metadata_file(name: &str, browser: &str) -> io::Result<PathBuf> {
+    Ok(manifest_file(name, browser)?.with_file_name(format!("{}.nm-meta.json", name)))
+}
+
+/// Installs the manifest file for the given browsers, like `install`, and records an
+/// `InstallMetadata` sidecar file next to each manifest for auditing.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::install::manifest::install_with_metadata;
+///
+/// install_with_metadata("my_extension", "An example extension", "/path/to/extension", &["chrome", "firefox"])
+///     .expect("Failed to install extension");
+/// ```
+pub fn install_with_metadata(
+    name: &str,
+    description: &str,
+    path: &str,
+    browsers: &[&str],
+) -> io::Result<()> {
+    install(name, description, path, browsers)?;
+
+    let installed_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(io::Error::other)?
+        .as_secs();
+    let metadata = InstallMetadata {
+        installed_at,
+        crate_version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    let metadata_json = serde_json::to_string_pretty(&metadata)
+        .map_err(|e| io::Error::other(format!("Serialization failed: {}", e)))?;
+    for &browser in browsers {
+        if let Ok(metadata_file) = metadata_file(name, browser) {
+            write_file(&metadata_file, &metadata_json)?;
+        }
+    }
+    Ok(())
+}
+
+/// Reads the `InstallMetadata` sidecar written by `install_with_metadata` for a browser.
+///
+/// Returns `None` if the manifest was installed without metadata.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::install::manifest::read_metadata;
+///
+/// if let Some(metadata) = read_metadata("my_extension", "chrome").expect("Failed to read metadata") {
+///     println!("Installed by version {} at {}", metadata.crate_version, metadata.installed_at);
+/// }
+/// ```
+pub fn read_metadata(name: &str, browser: &str) -> io::Result<Option<InstallMetadata>> {
+    let metadata_file = metadata_file(name, browser)?;
+    if !metadata_file.exists() {
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(metadata_file)?;
+    Ok(Some(serde_json::from_str(&contents)?))
+}
+
+/// Installs several caller-assembled manifests for a single browser in one call.
+///
+/// Each manifest is written as with `install_from_struct`. Returns the paths of the written
+/// manifest files, in the order given.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::install::manifest::{install_list, Manifest};
+/// use std::path::PathBuf;
+///
+/// let manifests: Vec<Manifest> = ["host_a", "host_b"]
+///     .iter()
+///     .map(|name| Manifest {
+///         name: name.to_string(),
+///         description: "An example extension".to_string(),
+///         path: PathBuf::from(format!("/opt/{}/host", name)),
+///         allowed_origins: None,
+///         allowed_extensions: Some(vec!["my_extension@example.org".to_string()]),
+///     })
+///     .collect();
+/// let written = install_list(&manifests, "firefox").expect("Failed to install manifests");
+/// assert_eq!(written.len(), 2);
+/// ```
+pub fn install_list(manifests: &[Manifest], browser: &str) -> io::Result<Vec<PathBuf>> {
+    let mut written = Vec::with_capacity(manifests.len());
+    for manifest in manifests {
+        install_from_struct(browser, manifest)?;
+        written.push(manifest_file(&manifest.name, browser)?);
+    }
+    Ok(written)
+}
+
+/// The outcome of a `remove_list` call.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RemovedManifests {
+    /// Manifest files that were deleted.
+    pub removed: Vec<PathBuf>,
+    /// Manifest files that were not installed, so there was nothing to delete.
+    pub missing: Vec<PathBuf>,
+}
+
+/// Removes the manifest files of several hosts for the specified browsers in one call.
+///
+/// Manifests that are not installed are recorded in `missing` rather than treated as
+/// errors. The `InstallMetadata` sidecar of each host is removed either way. Browsers not
+/// supported on this OS are skipped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::install::manifest::remove_list;
+///
+/// let outcome = remove_list(&["host_a", "host_b"], &["chrome", "firefox"])
+///     .expect("Failed to remove manifests");
+/// for path in &outcome.removed {
+///     println!("Removed {}", path.display());
+/// }
+/// for path in &outcome.missing {
+///     println!("Not installed: {}", path.display());
+/// }
+/// ```
+pub fn remove_list(names: &[&str], browsers: &[&str]) -> io::Result<RemovedManifests> {
+    let mut outcome = RemovedManifests::default();
+    for &name in names {
+        for &browser in browsers {
+            let Ok(manifest_file) = manifest_file(name, browser) else {
+                continue;
+            };
+            match fs::remove_file(&manifest_file) {
+                Ok(()) => outcome.removed.push(manifest_file),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    outcome.missing.push(manifest_file)
                 }
+                Err(e) => return Err(e),
+            }
+            let metadata_file = metadata_file(name, browser)?;
+            if metadata_file.exists() {
+                fs::remove_file(metadata_file)?;
             }
         }
     }
+    Ok(outcome)
+}
+
+fn is_installed(name: &str, browser: &str) -> io::Result<bool> {
+    match manifest_file(name, browser) {
+        Ok(manifest_file) => manifest_file.try_exists(),
+        Err(_) => Ok(false),
+    }
+}
+
+fn is_installed_for_all(name: &str, browsers: &[&str]) -> io::Result<bool> {
+    for &browser in browsers {
+        if !is_installed(name, browser)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Verifies many hosts at once, returning whether each host's manifest is installed for
+/// every one of the given browsers.
+///
+/// With the `parallel` feature enabled, hosts are checked concurrently using `rayon`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::install::manifest::batch_verify;
+///
+/// let results = batch_verify(&["host_a", "host_b"], &["chrome", "firefox"])
+///     .expect("Verification failed");
+/// for (name, installed) in results {
+///     println!("{}: {}", name, if installed { "installed" } else { "missing" });
+/// }
+/// ```
+pub fn batch_verify(names: &[&str], browsers: &[&str]) -> io::Result<HashMap<String, bool>> {
+    #[cfg(feature = "parallel")]
+    let names = names.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let names = names.iter();
+
+    names
+        .map(|&name| Ok((name.to_string(), is_installed_for_all(name, browsers)?)))
+        .collect()
+}
+
+/// Bundles the arguments of a single `install` call for `batch_install`.
+#[derive(Debug, Clone)]
+pub struct InstallSpec {
+    pub name: String,
+    pub description: String,
+    pub path: String,
+    pub browsers: Vec<String>,
+}
+
+/// Installs many hosts at once, returning one result per `InstallSpec`, in the order given.
+///
+/// With the `parallel` feature enabled, manifests are written concurrently using `rayon`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::install::manifest::{batch_install, InstallSpec};
+///
+/// let specs = vec![InstallSpec {
+///     name: "my_extension".to_string(),
+///     description: "An example extension".to_string(),
+///     path: "/path/to/extension".to_string(),
+///     browsers: vec!["chrome".to_string(), "firefox".to_string()],
+/// }];
+/// for result in batch_install(&specs) {
+///     result.expect("Failed to install extension");
+/// }
+/// ```
+pub fn batch_install(installs: &[InstallSpec]) -> Vec<io::Result<()>> {
+    #[cfg(feature = "parallel")]
+    let installs = installs.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let installs = installs.iter();
+
+    installs
+        .map(|spec| {
+            let browsers: Vec<&str> = spec.browsers.iter().map(String::as_str).collect();
+            install(&spec.name, &spec.description, &spec.path, &browsers)
+        })
+        .collect()
+}
+
+/// Writes a human-readable installation report for `name` to `w`.
+///
+/// For each supported browser the report shows the manifest path, whether the manifest
+/// exists, when and by which crate version it was installed if an `InstallMetadata` sidecar
+/// is present, whether it is valid JSON, and whether the executable it points to exists.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::install::manifest::install_report;
+///
+/// install_report(&mut std::io::stdout(), "my_extension").expect("Failed to write report");
+/// ```
+pub fn install_report<W: Write>(w: &mut W, name: &str) -> io::Result<()> {
+    let browser_info = get_browser_info();
+    let mut browsers: Vec<&String> = browser_info.keys().collect();
+    browsers.sort();
+
+    for browser in browsers {
+        let manifest_file = match manifest_file(name, browser) {
+            Ok(manifest_file) => manifest_file,
+            Err(_) => {
+                writeln!(w, "{}: not supported on this OS", browser)?;
+                continue;
+            }
+        };
+        writeln!(w, "{}: {}", browser, manifest_file.display())?;
+        if !manifest_file.exists() {
+            writeln!(w, "  manifest:   missing")?;
+            continue;
+        }
+        writeln!(w, "  manifest:   installed")?;
+        match read_metadata(name, browser) {
+            Ok(Some(metadata)) => writeln!(
+                w,
+                "  metadata:   installed at {} by version {}",
+                metadata.installed_at, metadata.crate_version
+            )?,
+            Ok(None) => {}
+            Err(e) => writeln!(w, "  metadata:   invalid ({})", e)?,
+        }
+
+        let manifest = match parse_manifest_file(&manifest_file) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                writeln!(w, "  json:       invalid ({})", e)?;
+                continue;
+            }
+        };
+        writeln!(w, "  json:       valid")?;
+
+        if manifest.path.is_file() {
+            writeln!(w, "  executable: {}", manifest.path.display())?;
+        } else {
+            writeln!(w, "  executable: missing ({})", manifest.path.display())?;
+        }
+    }
+    Ok(())
+}
+
+/// Checks that a manifest JSON value contains the fields a browser requires for `host_name`.
+///
+/// `browser` selects which allow list is required: `allowed_origins` for Chromium-family
+/// browsers and `allowed_extensions` for Firefox-family browsers. On failure, every problem
+/// found is returned.
+///
+/// # Examples
+///
+/// ```
+/// use native_messaging::install::manifest::verify_manifest_has_required_fields;
+/// use serde_json::json;
+///
+/// let manifest = json!({
+///     "name": "my_extension",
+///     "description": "An example extension",
+///     "path": "/opt/my_extension/host",
+///     "type": "stdio",
+///     "allowed_extensions": ["my_extension@example.org"],
+/// });
+/// assert!(verify_manifest_has_required_fields(&manifest, "firefox", "my_extension").is_ok());
+///
+/// let errors = verify_manifest_has_required_fields(&manifest, "chrome", "other").unwrap_err();
+/// assert_eq!(errors.len(), 2);
+/// ```
+pub fn verify_manifest_has_required_fields(
+    manifest: &serde_json::Value,
+    browser: &str,
+    host_name: &str,
+) -> Result<(), Vec<String>> {
+    let Some(object) = manifest.as_object() else {
+        return Err(vec!["manifest is not a JSON object".to_string()]);
+    };
+    let mut errors = Vec::new();
+
+    match object.get("name").and_then(|v| v.as_str()) {
+        Some(name) if name == host_name => {}
+        Some(name) => errors.push(format!(
+            "name is {:?} but the host name is {:?}",
+            name, host_name
+        )),
+        None => errors.push("name is missing or not a string".to_string()),
+    }
+    if object.get("description").and_then(|v| v.as_str()).is_none() {
+        errors.push("description is missing or not a string".to_string());
+    }
+    match object.get("path").and_then(|v| v.as_str()) {
+        Some(path) if Path::new(path).is_absolute() => {}
+        Some(path) => errors.push(format!("path is not absolute: {}", path)),
+        None => errors.push("path is missing or not a string".to_string()),
+    }
+    if object.get("type").and_then(|v| v.as_str()) != Some("stdio") {
+        errors.push("type must be \"stdio\"".to_string());
+    }
+    if let Some(field) = allowed_ids_field(browser) {
+        let is_string_list = object
+            .get(field)
+            .and_then(|v| v.as_array())
+            .is_some_and(|ids| ids.iter().all(|id| id.is_string()));
+        if !is_string_list {
+            errors.push(format!("{} is missing or not a list of strings", field));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn allowed_ids_field(browser: &str) -> Option<&'static str> {
+    if is_chromium_family(browser) {
+        Some("allowed_origins")
+    } else if is_firefox_family(browser) {
+        Some("allowed_extensions")
+    } else {
+        None
+    }
+}
+
+/// Reads and parses a native messaging manifest file from any location.
+///
+/// Whichever of `allowed_origins` and `allowed_extensions` the file contains is kept, so both
+/// Chrome and Firefox manifests can be read.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::install::manifest::parse_manifest_file;
+/// use std::path::Path;
+///
+/// let manifest = parse_manifest_file(Path::new("/etc/opt/chrome/native-messaging-hosts/my_extension.json"))
+///     .expect("Failed to read manifest");
+/// println!("{} -> {}", manifest.name, manifest.path.display());
+/// ```
+///
+/// # Errors
+/// Returns an `io::Error` if the file cannot be read, or one of kind `InvalidData` if it is
+/// not a valid manifest.
+pub fn parse_manifest_file(path: &Path) -> io::Result<Manifest> {
+    let contents = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+/// Updates the `crate_version` recorded in a browser's `InstallMetadata` sidecar to the
+/// version of this crate, keeping the original installation time.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::install::manifest::upgrade_metadata;
+///
+/// upgrade_metadata("my_extension", "chrome").expect("Failed to upgrade metadata");
+/// ```
+///
+/// # Errors
+/// Returns an `io::Error` of kind `NotFound` if the manifest was installed without metadata.
+pub fn upgrade_metadata(name: &str, browser: &str) -> io::Result<()> {
+    let mut metadata = read_metadata(name, browser)?.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("No install metadata for {} in {}", name, browser),
+        )
+    })?;
+    metadata.crate_version = env!("CARGO_PKG_VERSION").to_string();
+
+    let metadata_json = serde_json::to_string_pretty(&metadata)
+        .map_err(|e| io::Error::other(format!("Serialization failed: {}", e)))?;
+    write_file(&metadata_file(name, browser)?, &metadata_json)
+}
+
+fn check_allowed_ids(browser: &str, manifest: &Manifest) -> io::Result<()> {
+    if is_chromium_family(browser) {
+        for origin in manifest.allowed_origins.iter().flatten() {
+            verify_chrome_origin_format(origin)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        }
+    }
+    if is_firefox_family(browser) {
+        for id in manifest.allowed_extensions.iter().flatten() {
+            verify_firefox_extension_id_format(id)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Checks that a Chrome `allowed_origins` entry has the form
+/// `chrome-extension://<extension id>/`.
+///
+/// Chrome extension IDs are 32 characters long and use only the letters `a` to `p`.
+///
+/// # Examples
+///
+/// ```
+/// use native_messaging::install::manifest::verify_chrome_origin_format;
+///
+/// assert!(verify_chrome_origin_format("chrome-extension://abcdefghijklmnopabcdefghijklmnop/").is_ok());
+/// assert!(verify_chrome_origin_format("chrome-extension://abcdefghijklmnopabcdefghijklmnop").is_err());
+/// ```
+///
+/// # Errors
+/// Returns a message describing which part of the origin is wrong.
+pub fn verify_chrome_origin_format(origin: &str) -> Result<(), String> {
+    const PREFIX: &str = "chrome-extension://";
+
+    let Some(rest) = origin.strip_prefix(PREFIX) else {
+        return Err(format!("origin {:?} must start with {:?}", origin, PREFIX));
+    };
+    let Some(id) = rest.strip_suffix('/') else {
+        return Err(format!(
+            "origin {:?} must end with a trailing slash",
+            origin
+        ));
+    };
+    if id.len() != 32 {
+        return Err(format!(
+            "extension ID {:?} must be 32 characters long, not {}",
+            id,
+            id.len()
+        ));
+    }
+    if !id.chars().all(|c| ('a'..='p').contains(&c)) {
+        return Err(format!(
+            "extension ID {:?} may only contain the letters a to p",
+            id
+        ));
+    }
     Ok(())
 }
+
+/// Checks that a Firefox `allowed_extensions` entry is a valid add-on ID: either a GUID in
+/// braces, such as `{daf44bf7-a45e-4450-979c-91cf07434c3d}`, or an email-like ID, such as
+/// `my_extension@example.org`.
+///
+/// # Examples
+///
+/// ```
+/// use native_messaging::install::manifest::verify_firefox_extension_id_format;
+///
+/// assert!(verify_firefox_extension_id_format("my_extension@example.org").is_ok());
+/// assert!(verify_firefox_extension_id_format("{daf44bf7-a45e-4450-979c-91cf07434c3d}").is_ok());
+/// assert!(verify_firefox_extension_id_format("my_extension").is_err());
+/// ```
+///
+/// # Errors
+/// Returns a message explaining the accepted formats.
+pub fn verify_firefox_extension_id_format(id: &str) -> Result<(), String> {
+    let is_id_char = |c: char| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_');
+
+    let is_uuid = id
+        .strip_prefix('{')
+        .and_then(|rest| rest.strip_suffix('}'))
+        .is_some_and(|uuid| {
+            let groups: Vec<&str> = uuid.split('-').collect();
+            groups.len() == 5
+                && groups.iter().zip([8, 4, 4, 4, 12]).all(|(group, len)| {
+                    group.len() == len && group.chars().all(|c| c.is_ascii_hexdigit())
+                })
+        });
+    let is_email_like = id.split_once('@').is_some_and(|(local, domain)| {
+        !domain.is_empty() && local.chars().all(is_id_char) && domain.chars().all(is_id_char)
+    });
+
+    if is_uuid || is_email_like {
+        Ok(())
+    } else {
+        Err(format!(
+            "extension ID {:?} must be {{uuid}} or email-like",
+            id
+        ))
+    }
+}
+
+/// Generates the manifest `install` would write for every browser supported on this OS,
+/// keyed by browser, without writing anything.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::install::manifest::generate_manifest_hash_map;
+///
+/// let manifests = generate_manifest_hash_map("my_extension", "An example extension", "/path/to/extension")
+///     .expect("Failed to generate manifests");
+/// for (browser, manifest) in &manifests {
+///     println!("{}: {}", browser, manifest);
+/// }
+/// ```
+pub fn generate_manifest_hash_map(
+    name: &str,
+    description: &str,
+    path: &str,
+) -> io::Result<HashMap<String, serde_json::Value>> {
+    let manifest = new_manifest(name, description, path)?;
+    let mut manifests = HashMap::new();
+    for (browser, info) in get_browser_info() {
+        if info.for_current_os().is_some() {
+            let manifest = serde_json::to_value(manifest_for_browser(&browser, &manifest))?;
+            manifests.insert(browser, manifest);
+        }
+    }
+    Ok(manifests)
+}
+
+/// Installs the manifest file for the given browsers, like `install`, and returns the
+/// manifest written for each browser.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::install::manifest::install_and_return;
+///
+/// let written = install_and_return("my_extension", "An example extension", "/path/to/extension", &["chrome", "firefox"])
+///     .expect("Failed to install extension");
+/// for (browser, manifest) in &written {
+///     println!("{}: {}", browser, manifest.path.display());
+/// }
+/// ```
+pub fn install_and_return(
+    name: &str,
+    description: &str,
+    path: &str,
+    browsers: &[&str],
+) -> io::Result<HashMap<String, Manifest>> {
+    let manifest = new_manifest(name, description, path)?;
+    let written = std::cell::RefCell::new(HashMap::new());
+    install_unix(browsers, &manifest, &|browser, _| {
+        written.borrow_mut().insert(
+            browser.to_string(),
+            manifest_for_browser(browser, &manifest),
+        );
+    })?;
+    Ok(written.into_inner())
+}
+
+/// Returns the path of the Chrome manifest file for `host_name` in the current user's
+/// directory.
+///
+/// # Examples
+///
+/// ```no_run
+/// use native_messaging::install::manifest::chrome_user_manifest;
+///
+/// println!("{}", chrome_user_manifest("my_extension").display());
+/// ```
+///
+/// # Panics
+/// Panics if Chrome has no manifest directory on this OS.
+pub fn chrome_user_manifest(host_name: &str) -> PathBuf {
+    manifest_file(host_name, "chrome").expect("Chrome is not supported on this OS")
+}
+
+/// Returns the path of the Firefox manifest file for `host_name` in the current user's
+/// directory.
+///
+/// # Panics
+/// Panics if Firefox has no manifest directory on this OS.
+pub fn firefox_user_manifest(host_name: &str) -> PathBuf {
+    manifest_file(host_name, "firefox").expect("Firefox is not supported on this OS")
+}
+
+/// Returns the path of the Edge manifest file for `host_name` in the current user's
+/// directory.
+///
+/// # Panics
+/// Panics if Edge has no manifest directory on this OS.
+pub fn edge_user_manifest(host_name: &str) -> PathBuf {
+    manifest_file(host_name, "edge").expect("Edge is not supported on this OS")
+}
+
+/// Returns the path of the Brave manifest file for `host_name` in the current user's
+/// directory.
+///
+/// # Panics
+/// Panics if Brave has no manifest directory on this OS.
+pub fn brave_user_manifest(host_name: &str) -> PathBuf {
+    manifest_file(host_name, "brave").expect("Brave is not supported on this OS")
+}
+
+/// Returns the path of the Vivaldi manifest file for `host_name` in the current user's
+/// directory.
+///
+/// # Panics
+/// Panics if Vivaldi has no manifest directory on this OS.
+pub fn vivaldi_user_manifest(host_name: &str) -> PathBuf {
+    manifest_file(host_name, "vivaldi").expect("Vivaldi is not supported on this OS")
+}
+
+/// Returns the path of the Chromium manifest file for `host_name` in the current user's
+/// directory.
+///
+/// # Panics
+/// Panics if Chromium has no manifest directory on this OS.
+pub fn chromium_user_manifest(host_name: &str) -> PathBuf {
+    manifest_file(host_name, "chromium").expect("Chromium is not supported on this OS")
+}
+
+/// Returns the path of the LibreWolf manifest file for `host_name` in the current user's
+/// directory.
+///
+/// # Panics
+/// Panics if LibreWolf has no manifest directory on this OS.
+pub fn librewolf_user_manifest(host_name: &str) -> PathBuf {
+    manifest_file(host_name, "librewolf").expect("LibreWolf is not supported on this OS")
+}
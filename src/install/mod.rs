@@ -1 +1,6 @@
 pub mod manifest;
+
+pub use manifest::{
+    brave_user_manifest, chrome_user_manifest, chromium_user_manifest, edge_user_manifest,
+    firefox_user_manifest, librewolf_user_manifest, vivaldi_user_manifest,
+};
@@ -1,2 +1,4 @@
 pub mod host;
 pub mod install;
+
+pub use host::{MAX_FROM_BROWSER, MAX_TO_BROWSER};
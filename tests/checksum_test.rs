@@ -0,0 +1,38 @@
+#![cfg(all(feature = "checksum", unix))]
+
+use native_messaging::install::manifest::{checksum_manifest, compare_checksum, get_browser_info};
+use std::fs;
+
+// SHA-256 of the four bytes `test`.
+const TEST_SHA256: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+/// Points HOME at a scratch directory and installs a chrome "manifest" containing `test`.
+///
+/// Every test in this binary writes the same content to the same place, so running them in
+/// parallel is harmless, and no other test binary is affected by the HOME change.
+fn install_known_manifest() {
+    let home = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("checksum_test_home");
+    std::env::set_var("HOME", &home);
+
+    let browser_info = get_browser_info();
+    let manifest_path = browser_info["chrome"].for_current_os().unwrap();
+    fs::create_dir_all(manifest_path).unwrap();
+    fs::write(manifest_path.join("my_extension.json"), "test").unwrap();
+}
+
+#[test]
+fn test_checksum_manifest_of_known_file() {
+    install_known_manifest();
+    assert_eq!(
+        checksum_manifest("my_extension", "chrome").unwrap(),
+        TEST_SHA256
+    );
+}
+
+#[test]
+fn test_compare_checksum_ignores_case() {
+    install_known_manifest();
+    assert!(compare_checksum("my_extension", "chrome", TEST_SHA256).unwrap());
+    assert!(compare_checksum("my_extension", "chrome", &TEST_SHA256.to_uppercase()).unwrap());
+    assert!(!compare_checksum("my_extension", "chrome", &"0".repeat(64)).unwrap());
+}
@@ -0,0 +1,68 @@
+use native_messaging::host::{encode_message, get_message, watch_for_disconnect};
+use serde_json::json;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+const CHILD_ENV: &str = "NATIVE_MESSAGING_DISCONNECT_CHILD";
+const READY: &str = "native-messaging-disconnect-child-ready";
+
+/// Runs inside the child process spawned by `test_message_after_cancelled_watcher_is_read`,
+/// acting as a host whose stdin is a pipe.
+#[tokio::test]
+#[ignore = "run as a child process by test_message_after_cancelled_watcher_is_read"]
+async fn disconnect_child() {
+    if std::env::var_os(CHILD_ENV).is_none() {
+        return;
+    }
+
+    tokio::select! {
+        _ = watch_for_disconnect() => std::process::exit(2),
+        _ = tokio::task::spawn_blocking(|| std::thread::sleep(Duration::from_millis(100))) => {}
+    }
+
+    // Written straight to stdout, which the test harness does not capture, so the parent
+    // knows the watcher has been cancelled before it sends the frame.
+    let mut stdout = std::io::stdout();
+    writeln!(stdout, "{}", READY).unwrap();
+    stdout.flush().unwrap();
+
+    let message = get_message().await.unwrap();
+    let code = if message == r#"{"key":"value"}"# {
+        0
+    } else {
+        1
+    };
+    std::process::exit(code);
+}
+
+#[test]
+fn test_message_after_cancelled_watcher_is_read() {
+    let mut child = Command::new(std::env::current_exe().unwrap())
+        .args([
+            "--exact",
+            "disconnect_child",
+            "--ignored",
+            "--test-threads",
+            "1",
+        ])
+        .env(CHILD_ENV, "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+    let ready = stdout
+        .lines()
+        .any(|line| line.is_ok_and(|line| line.ends_with(READY)));
+    assert!(ready, "child exited before cancelling the watcher");
+
+    let mut stdin = child.stdin.take().unwrap();
+    stdin
+        .write_all(&encode_message(&json!({ "key": "value" })).unwrap())
+        .unwrap();
+    drop(stdin);
+
+    assert_eq!(child.wait().unwrap().code(), Some(0));
+}
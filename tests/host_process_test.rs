@@ -0,0 +1,17 @@
+#![cfg(unix)]
+
+use native_messaging::host::spawn_host_process;
+use serde_json::{json, Value};
+
+#[test]
+fn test_host_process_round_trip() {
+    // `cat` echoes every frame back unchanged, which makes it a minimal echo host.
+    let mut host = spawn_host_process("cat", &[]).unwrap();
+
+    let message = json!({ "key": "value" });
+    host.send(&message).unwrap();
+    let reply: Value = host.recv().unwrap();
+    assert_eq!(reply, message);
+
+    assert!(host.wait().unwrap().success());
+}
@@ -0,0 +1,145 @@
+use native_messaging::install::manifest::{
+    generate_manifest_hash_map, install_chromium_only, install_firefox_only, install_from_struct,
+    remove_list, verify_chrome_origin_format, verify_firefox_extension_id_format,
+    verify_manifest_has_required_fields, Manifest,
+};
+
+#[test]
+fn test_manifest_builder_rejects_invalid_name() {
+    let err = Manifest::builder()
+        .name("my-extension")
+        .description("An example extension")
+        .path("/opt/my_extension/host")
+        .build()
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+    let err = Manifest::builder()
+        .name("My_Extension")
+        .description("An example extension")
+        .path("/opt/my_extension/host")
+        .build()
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+    let err = Manifest::builder()
+        .name("com..example")
+        .description("An example extension")
+        .path("/opt/my_extension/host")
+        .build()
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_manifest_builder_requires_path() {
+    let err = Manifest::builder()
+        .name("com.example.host")
+        .description("An example extension")
+        .build()
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_verify_chrome_origin_format() {
+    assert!(
+        verify_chrome_origin_format("chrome-extension://knldjmfmopnpolahpmmgbagdohdnhkik/").is_ok()
+    );
+
+    // Missing scheme, missing trailing slash, wrong length, and letters outside a-p.
+    assert!(verify_chrome_origin_format("knldjmfmopnpolahpmmgbagdohdnhkik/").is_err());
+    assert!(
+        verify_chrome_origin_format("chrome-extension://knldjmfmopnpolahpmmgbagdohdnhkik").is_err()
+    );
+    assert!(
+        verify_chrome_origin_format("chrome-extension://knldjmfmopnpolahpmmgbagdohdnhk/").is_err()
+    );
+    assert!(
+        verify_chrome_origin_format("chrome-extension://knldjmfmopnpolahpmmgbagdohdnhkiz/")
+            .is_err()
+    );
+}
+
+#[test]
+fn test_verify_firefox_extension_id_format() {
+    assert!(verify_firefox_extension_id_format("my_extension@example.org").is_ok());
+    assert!(verify_firefox_extension_id_format("{daf44bf7-a45e-4450-979c-91cf07434c3d}").is_ok());
+
+    assert!(verify_firefox_extension_id_format("my_extension").is_err());
+    assert!(verify_firefox_extension_id_format("my_extension@").is_err());
+    assert!(verify_firefox_extension_id_format("daf44bf7-a45e-4450-979c-91cf07434c3d").is_err());
+    assert!(verify_firefox_extension_id_format("{daf44bf7-a45e-4450-979c}").is_err());
+}
+
+#[test]
+fn test_install_single_family_rejects_other_browsers() {
+    let err = install_chromium_only(
+        "my_extension",
+        "An example extension",
+        "/nonexistent",
+        &[],
+        &["firefox"],
+    )
+    .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+    let err = install_firefox_only(
+        "my_extension",
+        "An example extension",
+        "/nonexistent",
+        &[],
+        &["chrome"],
+    )
+    .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_generated_manifests_pass_required_fields_check() {
+    let exe = std::env::current_exe().unwrap();
+    let manifests = generate_manifest_hash_map(
+        "my_extension",
+        "An example extension",
+        exe.to_str().unwrap(),
+    )
+    .unwrap();
+    assert!(!manifests.is_empty());
+
+    for (browser, manifest) in &manifests {
+        assert_eq!(manifest["type"], "stdio");
+        assert_eq!(
+            verify_manifest_has_required_fields(manifest, browser, "my_extension"),
+            Ok(()),
+            "{}",
+            browser
+        );
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_remove_list_records_missing_manifests() {
+    let outcome = remove_list(
+        &["native_messaging_test_not_installed"],
+        &["chrome", "firefox"],
+    )
+    .unwrap();
+    assert!(outcome.removed.is_empty());
+    assert_eq!(outcome.missing.len(), 2);
+}
+
+#[test]
+fn test_install_from_struct_rejects_invalid_name() {
+    for name in ["../x", "my-ext"] {
+        let manifest = Manifest {
+            name: name.to_string(),
+            description: "An example extension".to_string(),
+            path: "/opt/my_extension/host".into(),
+            allowed_origins: None,
+            allowed_extensions: None,
+        };
+        let err = install_from_struct("chrome", &manifest).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+}
@@ -1,5 +1,9 @@
-use native_messaging::host::encode_message;
+use native_messaging::host::{
+    chunked_send, encode_message, read_message_body, read_message_header, FramedReader,
+    MAX_FROM_BROWSER, MAX_TO_BROWSER,
+};
 use serde_json::json;
+use std::io::Cursor;
 
 #[tokio::test]
 async fn test_encode_message() {
@@ -16,3 +20,63 @@ async fn test_encode_message() {
     let decoded_message: serde_json::Value = serde_json::from_slice(content_bytes).unwrap();
     assert_eq!(decoded_message, message);
 }
+
+#[test]
+fn test_read_message_header_and_body() {
+    let message = json!({ "key": "value" });
+    let mut reader = Cursor::new(encode_message(&message).unwrap());
+
+    let length = read_message_header(&mut reader).unwrap();
+    let content = read_message_body(&mut reader, length, 1024).unwrap();
+    let decoded_message: serde_json::Value = serde_json::from_str(&content).unwrap();
+    assert_eq!(decoded_message, message);
+}
+
+#[test]
+fn test_read_message_body_rejects_oversized_message() {
+    let mut reader = Cursor::new(encode_message(&json!({ "key": "value" })).unwrap());
+
+    let length = read_message_header(&mut reader).unwrap();
+    let err = read_message_body(&mut reader, length, 4).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_framed_reader_stops_at_end_of_input() {
+    let mut recording = encode_message(&json!({ "n": 1 })).unwrap();
+    recording.extend(encode_message(&json!({ "n": 2 })).unwrap());
+
+    let mut reader = FramedReader::new(recording.as_slice(), MAX_FROM_BROWSER);
+    assert_eq!(reader.next().unwrap().unwrap(), r#"{"n":1}"#);
+    assert_eq!(reader.next().unwrap().unwrap(), r#"{"n":2}"#);
+    assert!(reader.next().is_none());
+
+    // A truncated length prefix is an error rather than a clean end of input.
+    let mut reader = FramedReader::new(&[1u8, 0][..], MAX_FROM_BROWSER);
+    let err = reader.next().unwrap().unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn test_framed_reader_stops_after_error() {
+    let mut recording = encode_message(&json!({ "key": "a long enough value" })).unwrap();
+    recording.extend(encode_message(&json!({ "n": 2 })).unwrap());
+
+    // The oversized body is left unread, so the reader must not carry on from inside it.
+    let mut reader = FramedReader::new(recording.as_slice(), 8);
+    let err = reader.next().unwrap().unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(reader.next().is_none());
+    assert!(reader.next().is_none());
+}
+
+#[tokio::test]
+async fn test_chunked_send_rejects_oversized_chunk_before_sending() {
+    // The oversized chunk comes second, so nothing may be written before it is rejected.
+    let chunks = vec![
+        json!({ "rows": [1, 2, 3] }),
+        json!({ "rows": "x".repeat(MAX_TO_BROWSER) }),
+    ];
+    let err = chunked_send(&chunks, "chunk_id").await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}